@@ -0,0 +1,243 @@
+//! A framed, self-describing record format for cached values, validated once up front so C++ can then read fields
+//! directly out of the underlying buffer with no further copying or deserialization -- the "validate the archive,
+//! then access in place" pattern.
+//!
+//! Layout, all integers little-endian:
+//!
+//! ```text
+//! magic: u32          -- MAGIC
+//! version: u16        -- VERSION
+//! field_count: u16
+//! fields: [ (offset: u32, len: u32) ; field_count ]
+//! payload: [u8]        -- the field ranges above point into here
+//! checksum: u32        -- fnv1a32 of everything before this
+//! ```
+
+use bytes::Bytes;
+
+use crate::{Error, ErrorCode, Result};
+
+const MAGIC: u32 = 0xCA4E_5201;
+const VERSION: u16 = 1;
+const HEADER_PREFIX_LEN: usize = 8; // magic(4) + version(2) + field_count(2)
+const FIELD_DESC_LEN: usize = 8; // offset(4) + len(4)
+const CHECKSUM_LEN: usize = 4;
+
+/// Upper bound on a single field's length, so a corrupt or hostile length can't be used to read far past the end
+/// of the buffer before the bounds check below even runs.
+const MAX_FIELD_LEN: usize = 1 << 20;
+/// Upper bound on `field_count`, so a corrupt count can't be used to make the header computation itself overflow.
+const MAX_FIELDS: usize = 4096;
+
+fn fnv1a32(data: &[u8]) -> u32 {
+    let mut hash: u32 = 0x811c_9dc5;
+    for &byte in data {
+        hash ^= byte as u32;
+        hash = hash.wrapping_mul(0x0100_0193);
+    }
+    hash
+}
+
+struct FieldRange {
+    offset: u32,
+    len: u32,
+}
+
+/// A validated archive: the field ranges below are guaranteed to lie inside the buffer they were validated
+/// against, so [`Archive::field`] never needs to bounds-check again.
+pub(crate) struct Archive {
+    fields: Vec<FieldRange>,
+}
+
+impl Archive {
+    /// Validate `bytes` as an archived record: header present, field ranges all 4-byte aligned and inside the
+    /// payload, and the trailing checksum matches. Any failure is wrapped with a frame naming this entry point on
+    /// top of the root cause. `cachers_response_data_validate` stores that chain as the response's error; only its
+    /// root-cause `ErrorCode` reaches C++, through `ResponseInfo::error_code`.
+    pub(crate) fn validate(bytes: &Bytes) -> Result<Self> {
+        Self::validate_inner(bytes).map_err(|e| {
+            let code = e.code();
+            e.context(code, "cachers_response_data_validate")
+        })
+    }
+
+    fn validate_inner(bytes: &Bytes) -> Result<Self> {
+        let buf = bytes.as_ref();
+        if buf.len() < HEADER_PREFIX_LEN + CHECKSUM_LEN {
+            return Err(Error::new(ErrorCode::InvalidArgument, format!(
+                "archive is {} bytes, too small to hold a header and checksum", buf.len()
+            )));
+        }
+
+        let magic = u32::from_le_bytes(buf[0..4].try_into().unwrap());
+        if magic != MAGIC {
+            return Err(Error::new(ErrorCode::InvalidArgument, format!("bad archive magic {magic:#010x}")));
+        }
+
+        let version = u16::from_le_bytes(buf[4..6].try_into().unwrap());
+        if version != VERSION {
+            return Err(Error::new(ErrorCode::InvalidArgument, format!("unsupported archive version {version}")));
+        }
+
+        let field_count = u16::from_le_bytes(buf[6..8].try_into().unwrap()) as usize;
+        if field_count > MAX_FIELDS {
+            return Err(Error::new(ErrorCode::InvalidArgument, format!(
+                "archive declares {field_count} fields, more than the {MAX_FIELDS} allowed"
+            )));
+        }
+
+        let header_len = HEADER_PREFIX_LEN + field_count * FIELD_DESC_LEN;
+        if buf.len() < header_len + CHECKSUM_LEN {
+            return Err(Error::new(ErrorCode::InvalidArgument, "archive header overruns the buffer"));
+        }
+        let payload_end = buf.len() - CHECKSUM_LEN;
+
+        let mut fields = Vec::with_capacity(field_count);
+        for i in 0..field_count {
+            let desc = HEADER_PREFIX_LEN + i * FIELD_DESC_LEN;
+            let offset = u32::from_le_bytes(buf[desc..desc + 4].try_into().unwrap());
+            let len = u32::from_le_bytes(buf[desc + 4..desc + 8].try_into().unwrap());
+
+            if offset % 4 != 0 {
+                return Err(Error::new(ErrorCode::InvalidArgument, format!(
+                    "field {i} offset {offset} is not 4-byte aligned"
+                )));
+            }
+            if len as usize > MAX_FIELD_LEN {
+                return Err(Error::new(ErrorCode::InvalidArgument, format!(
+                    "field {i} claims {len} bytes, more than the {MAX_FIELD_LEN} allowed"
+                )));
+            }
+            let end = (offset as usize).checked_add(len as usize).ok_or_else(|| {
+                Error::new(ErrorCode::InvalidArgument, format!("field {i} offset/length overflows"))
+            })?;
+            if (offset as usize) < header_len || end > payload_end {
+                return Err(Error::new(ErrorCode::InvalidArgument, format!(
+                    "field {i} range {offset}..{end} falls outside the payload ({header_len}..{payload_end})"
+                )));
+            }
+
+            fields.push(FieldRange { offset, len });
+        }
+
+        let expected_checksum = u32::from_le_bytes(buf[payload_end..].try_into().unwrap());
+        let actual_checksum = fnv1a32(&buf[..payload_end]);
+        if actual_checksum != expected_checksum {
+            return Err(Error::new(ErrorCode::InvalidArgument, format!(
+                "archive checksum mismatch: expected {expected_checksum:#010x}, computed {actual_checksum:#010x}"
+            )));
+        }
+
+        Ok(Self { fields })
+    }
+
+    pub(crate) fn field_count(&self) -> usize {
+        self.fields.len()
+    }
+
+    /// Byte range of field `index` within `bytes`, which must be the same buffer this archive was validated
+    /// against. Never panics or re-checks bounds -- that already happened in [`Archive::validate`].
+    pub(crate) fn field<'b>(&self, bytes: &'b Bytes, index: usize) -> Option<&'b [u8]> {
+        let range = self.fields.get(index)?;
+        Some(&bytes[range.offset as usize..(range.offset + range.len) as usize])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Build a well-formed archive out of `payload_fields`, each padded to a 4-byte boundary, with a correct
+    /// checksum -- so a test can corrupt exactly one byte and know the rest of the frame is otherwise valid.
+    fn build(payload_fields: &[&[u8]]) -> Vec<u8> {
+        let header_len = HEADER_PREFIX_LEN + payload_fields.len() * FIELD_DESC_LEN;
+
+        let mut payload = Vec::new();
+        let mut descs = Vec::new();
+        for field in payload_fields {
+            let offset = header_len + payload.len();
+            payload.extend_from_slice(field);
+            while payload.len() % 4 != 0 {
+                payload.push(0);
+            }
+            descs.push((offset as u32, field.len() as u32));
+        }
+
+        let mut buf = Vec::new();
+        buf.extend_from_slice(&MAGIC.to_le_bytes());
+        buf.extend_from_slice(&VERSION.to_le_bytes());
+        buf.extend_from_slice(&(payload_fields.len() as u16).to_le_bytes());
+        for (offset, len) in descs {
+            buf.extend_from_slice(&offset.to_le_bytes());
+            buf.extend_from_slice(&len.to_le_bytes());
+        }
+        buf.extend_from_slice(&payload);
+        let checksum = fnv1a32(&buf);
+        buf.extend_from_slice(&checksum.to_le_bytes());
+        buf
+    }
+
+    #[test]
+    fn validates_and_reads_back_fields() {
+        let bytes = Bytes::from(build(&[b"hello".as_slice(), b"world!!".as_slice()]));
+        let archive = Archive::validate(&bytes).unwrap();
+        assert_eq!(archive.field_count(), 2);
+        assert_eq!(archive.field(&bytes, 0), Some(b"hello".as_slice()));
+        assert_eq!(archive.field(&bytes, 1), Some(b"world!!".as_slice()));
+        assert_eq!(archive.field(&bytes, 2), None);
+    }
+
+    #[test]
+    fn rejects_buffer_too_small_for_a_header() {
+        assert!(Archive::validate(&Bytes::from_static(&[0u8; 4])).is_err());
+    }
+
+    #[test]
+    fn rejects_bad_magic() {
+        let mut bytes = build(&[b"x"]);
+        bytes[0] ^= 0xFF;
+        assert!(Archive::validate(&Bytes::from(bytes)).is_err());
+    }
+
+    #[test]
+    fn rejects_unsupported_version() {
+        let mut bytes = build(&[b"x"]);
+        bytes[4..6].copy_from_slice(&(VERSION + 1).to_le_bytes());
+        assert!(Archive::validate(&Bytes::from(bytes)).is_err());
+    }
+
+    #[test]
+    fn rejects_misaligned_field_offset() {
+        let mut bytes = build(&[b"x"]);
+        let desc_offset = HEADER_PREFIX_LEN;
+        let offset = u32::from_le_bytes(bytes[desc_offset..desc_offset + 4].try_into().unwrap());
+        bytes[desc_offset..desc_offset + 4].copy_from_slice(&(offset + 1).to_le_bytes());
+        assert!(Archive::validate(&Bytes::from(bytes)).is_err());
+    }
+
+    #[test]
+    fn rejects_field_range_outside_the_payload() {
+        let mut bytes = build(&[b"x"]);
+        let desc_len_offset = HEADER_PREFIX_LEN + 4;
+        // comfortably under MAX_FIELD_LEN, so this exercises the payload-bounds check rather than the length cap
+        bytes[desc_len_offset..desc_len_offset + 4].copy_from_slice(&(1u32 << 16).to_le_bytes());
+        assert!(Archive::validate(&Bytes::from(bytes)).is_err());
+    }
+
+    #[test]
+    fn rejects_checksum_mismatch() {
+        let mut bytes = build(&[b"x"]);
+        let last = bytes.len() - 1;
+        bytes[last] ^= 0xFF;
+        assert!(Archive::validate(&Bytes::from(bytes)).is_err());
+    }
+
+    #[test]
+    fn every_failure_is_wrapped_with_the_validating_entry_point() {
+        let Err(err) = Archive::validate(&Bytes::from_static(&[0u8; 4])) else {
+            panic!("expected validation to fail on a too-small buffer");
+        };
+        assert_eq!(err.depth(), 2);
+        assert!(err.at(0).unwrap().message().contains("cachers_response_data_validate"));
+    }
+}
@@ -0,0 +1,23 @@
+//! Pluggable backends responsible for actually fetching the bytes behind a key.
+//!
+//! [`Database`](crate::Database) doesn't know how a value is produced -- it only knows how to hand a key to a
+//! [`RequestBackend`] and get bytes back on a worker thread. The default backend just echoes the key, but the
+//! trait leaves room for a remote cache or HTTP origin to be swapped in later without touching the FFI surface.
+
+use bytes::Bytes;
+
+use crate::Result;
+
+pub(crate) trait RequestBackend: Send + Sync {
+    /// Fetch the value for `key`, blocking the calling (worker) thread until it is available.
+    fn fetch(&self, key: &[u8]) -> Result<Bytes>;
+}
+
+/// Backend used until a real one is configured: echoes the requested key back as the response body.
+pub(crate) struct EchoBackend;
+
+impl RequestBackend for EchoBackend {
+    fn fetch(&self, key: &[u8]) -> Result<Bytes> {
+        Ok(Bytes::copy_from_slice(key))
+    }
+}
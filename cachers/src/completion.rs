@@ -0,0 +1,173 @@
+//! A switchless completion queue: a bounded, lock-free MPSC ring of completed response tokens that C++ drains by
+//! polling instead of being called back into for every response. Workers are the producers; the single consumer is
+//! whichever C++ thread calls [`cachers_completion_poll`](crate::cachers_completion_poll).
+//!
+//! The ring is the classic bounded MPMC design (Dmitry Vyukov's), which happens to also work as a plain MPSC queue:
+//! each slot carries its own sequence number so producers racing for adjacent slots never block the consumer.
+
+use std::cell::UnsafeCell;
+use std::mem::MaybeUninit;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use crate::{Error, ErrorCode, NativeArc, Result, ResponseInner};
+
+struct Cell {
+    sequence: AtomicUsize,
+    value: UnsafeCell<MaybeUninit<*mut ResponseInner>>,
+}
+
+/// A bounded, lock-free ring of completed response tokens; see the module docs for the design.
+pub struct CompletionQueue {
+    buffer: Box<[Cell]>,
+    mask: usize,
+    head: AtomicUsize,
+    tail: AtomicUsize,
+}
+
+/// How many times a worker retries [`CompletionQueue::push`] before giving up and falling back to the
+/// function-pointer callback, if one was bound alongside the queue.
+pub(crate) const SWITCHLESS_PUSH_SPIN_ATTEMPTS: usize = 32;
+
+// safety: `Cell::value` is only ever touched through the sequence-number handshake below, which guarantees
+// exclusive access to whichever thread currently owns the slot
+unsafe impl Sync for CompletionQueue {}
+unsafe impl Send for CompletionQueue {}
+
+impl CompletionQueue {
+    pub(crate) fn new(capacity: usize) -> Result<Self> {
+        if capacity == 0 || !capacity.is_power_of_two() {
+            return Err(Error::new(ErrorCode::InvalidArgument, format!(
+                "completion queue capacity ({capacity}) must be a non-zero power of two"
+            )));
+        }
+
+        let buffer = (0..capacity)
+            .map(|i| Cell { sequence: AtomicUsize::new(i), value: UnsafeCell::new(MaybeUninit::uninit()) })
+            .collect();
+
+        Ok(Self { buffer, mask: capacity - 1, head: AtomicUsize::new(0), tail: AtomicUsize::new(0) })
+    }
+
+    /// Push `token` onto the ring. Returns `false` without blocking if the ring is full.
+    pub(crate) fn push(&self, token: *mut ResponseInner) -> bool {
+        let mut pos = self.head.load(Ordering::Relaxed);
+        loop {
+            let cell = &self.buffer[pos & self.mask];
+            let seq = cell.sequence.load(Ordering::Acquire);
+            let diff = seq as isize - pos as isize;
+            if diff == 0 {
+                match self.head.compare_exchange_weak(pos, pos + 1, Ordering::Relaxed, Ordering::Relaxed) {
+                    Ok(_) => {
+                        unsafe { (*cell.value.get()).write(token) };
+                        cell.sequence.store(pos + 1, Ordering::Release);
+                        return true;
+                    }
+                    Err(observed) => pos = observed,
+                }
+            } else if diff < 0 {
+                return false;
+            } else {
+                pos = self.head.load(Ordering::Relaxed);
+            }
+        }
+    }
+
+    /// Pop the next completed token, if any are ready. Only safe to call from a single consumer at a time.
+    pub(crate) fn pop(&self) -> Option<*mut ResponseInner> {
+        let pos = self.tail.load(Ordering::Relaxed);
+        let cell = &self.buffer[pos & self.mask];
+        let seq = cell.sequence.load(Ordering::Acquire);
+        let diff = seq as isize - (pos + 1) as isize;
+        if diff != 0 {
+            return None;
+        }
+
+        self.tail.store(pos + 1, Ordering::Relaxed);
+        let token = unsafe { (*cell.value.get()).assume_init_read() };
+        cell.sequence.store(pos + self.mask + 1, Ordering::Release);
+        Some(token)
+    }
+}
+
+impl Drop for CompletionQueue {
+    /// Any tokens a producer pushed but that `cachers_completion_poll` never drained are still holding an `Arc`
+    /// ref transferred to the C++ side; reclaim them here so releasing a non-empty queue doesn't leak them.
+    fn drop(&mut self) {
+        while let Some(token) = self.pop() {
+            // safety: `token` is a live `Arc::into_raw` pointer pushed by `ResponseInner::complete` and never
+            // handed back to C++ since nothing ever drained it
+            drop(unsafe { Arc::from_raw(token as *const ResponseInner) });
+        }
+    }
+}
+
+impl NativeArc for CompletionQueue {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ResponseInnerData;
+    use bytes::Bytes;
+    use std::sync::Mutex;
+
+    fn token() -> *mut ResponseInner {
+        let inner = Arc::new(ResponseInner { header: Bytes::new(), data: Mutex::new(ResponseInnerData::None) });
+        Arc::into_raw(inner) as *mut ResponseInner
+    }
+
+    unsafe fn release(token: *mut ResponseInner) {
+        drop(Arc::from_raw(token as *const ResponseInner));
+    }
+
+    #[test]
+    fn capacity_must_be_a_nonzero_power_of_two() {
+        assert!(CompletionQueue::new(0).is_err());
+        assert!(CompletionQueue::new(3).is_err());
+        assert!(CompletionQueue::new(4).is_ok());
+    }
+
+    #[test]
+    fn push_pop_round_trips_in_fifo_order() {
+        let queue = CompletionQueue::new(4).unwrap();
+        let (a, b) = (token(), token());
+        assert!(queue.push(a));
+        assert!(queue.push(b));
+        assert_eq!(queue.pop(), Some(a));
+        assert_eq!(queue.pop(), Some(b));
+        assert_eq!(queue.pop(), None);
+        unsafe { release(a); release(b) };
+    }
+
+    #[test]
+    fn push_fails_without_blocking_once_the_ring_is_full() {
+        let queue = CompletionQueue::new(2).unwrap();
+        let (a, b) = (token(), token());
+        assert!(queue.push(a));
+        assert!(queue.push(b));
+
+        let overflow = token();
+        assert!(!queue.push(overflow));
+        unsafe { release(overflow) }; // never entered the ring, so it's ours to free
+
+        assert_eq!(queue.pop(), Some(a));
+        let c = token();
+        assert!(queue.push(c)); // the slot `a` vacated is immediately reusable
+
+        while let Some(t) = queue.pop() {
+            unsafe { release(t) };
+        }
+    }
+
+    #[test]
+    fn drop_reclaims_tokens_left_in_the_ring() {
+        let queue = CompletionQueue::new(2).unwrap();
+        let inner = Arc::new(ResponseInner { header: Bytes::new(), data: Mutex::new(ResponseInnerData::None) });
+        let leftover = Arc::into_raw(inner.clone()) as *mut ResponseInner;
+        assert!(queue.push(leftover));
+        assert_eq!(Arc::strong_count(&inner), 2);
+
+        drop(queue);
+        assert_eq!(Arc::strong_count(&inner), 1);
+    }
+}
@@ -1,7 +1,21 @@
-use std::{cell::Cell, ffi, fmt, mem, ptr, sync::{Arc, Mutex, MutexGuard}, ops::{Deref, DerefMut}};
+use std::{cell::Cell, ffi, fmt, mem, ptr, thread, sync::{Arc, Mutex, MutexGuard}, ops::{Deref, DerefMut}};
 
 use bytes::Bytes;
 
+mod archive;
+mod backend;
+mod completion;
+mod version;
+mod worker;
+
+use archive::Archive;
+use backend::{EchoBackend, RequestBackend};
+use completion::{CompletionQueue, SWITCHLESS_PUSH_SPIN_ATTEMPTS};
+pub use version::{CachersVersion, CACHERS_FEATURE_ARCHIVED_VALUES, CACHERS_FEATURE_SWITCHLESS_QUEUE};
+use worker::{WorkerPool, DEFAULT_WORKER_COUNT};
+
+type CallbackFn = unsafe extern "C" fn(response: *const ResponseInfo, cxt: *mut ffi::c_void);
+
 #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
 #[repr(C)]
 #[must_use]
@@ -23,6 +37,7 @@ impl fmt::Display for ErrorCode {
 pub struct Error {
     code: ErrorCode,
     message: String,
+    source: Option<Box<Error>>,
 }
 
 impl fmt::Display for Error {
@@ -40,24 +55,55 @@ impl fmt::Debug for Error {
 impl std::error::Error for Error {}
 
 thread_local! {
-    static CURRENT_ERROR: Cell<Option<Error>> = Cell::new(None);
+    static CURRENT_ERROR: Cell<Option<Error>> = const { Cell::new(None) };
 }
 
 impl Error {
     pub fn new(code: ErrorCode, message: impl Into<String>) -> Self {
         let mut message = message.into();
         message.push('\0');
-        Self { code, message }
+        Self { code, message, source: None }
     }
 
+    /// Wrap `self` as the cause of a new, outer error -- anyhow-style context layering. `code`/`message` describe
+    /// this new frame; the chain's [`code()`](Error::code) keeps reporting the innermost (root cause) code, since
+    /// that's the classification callers actually need to react to.
+    pub fn context(self, code: ErrorCode, message: impl Into<String>) -> Self {
+        let mut message = message.into();
+        message.push('\0');
+        Self { code, message, source: Some(Box::new(self)) }
+    }
+
+    /// The `ErrorCode` of the root cause of this chain.
     pub fn code(&self) -> ErrorCode {
-        self.code
+        match &self.source {
+            Some(source) => source.code(),
+            None => self.code,
+        }
     }
 
     pub fn message(&self) -> &str {
         &self.message
     }
 
+    /// The next error in the chain, if this one was produced by [`Error::context`].
+    pub fn source(&self) -> Option<&Error> {
+        self.source.as_deref()
+    }
+
+    /// Number of frames in this chain, counting `self` as frame `0` (outermost) through the root cause.
+    pub fn depth(&self) -> usize {
+        1 + self.source.as_ref().map_or(0, |source| source.depth())
+    }
+
+    /// The frame `index` steps from the outermost (`0` is `self`).
+    pub fn at(&self, index: usize) -> Option<&Error> {
+        match index {
+            0 => Some(self),
+            _ => self.source.as_ref().and_then(|source| source.at(index - 1)),
+        }
+    }
+
     pub fn save_to_thread_local(self) {
         CURRENT_ERROR.with(|r| {
             r.set(Some(self))
@@ -88,6 +134,16 @@ pub extern "C" fn cachers_current_errstr() -> *const ffi::c_char {
     })
 }
 
+/// Borrow the current thread's stored error without disturbing it -- same take/set trick as `cachers_current_errstr`.
+fn with_current_error<R>(f: impl FnOnce(&Error) -> R) -> Option<R> {
+    CURRENT_ERROR.with(|r| {
+        let err = r.take()?;
+        let out = f(&err);
+        r.set(Some(err));
+        Some(out)
+    })
+}
+
 fn wrap_err_call<F>(f: F) -> ErrorCode
     where F: FnOnce() -> Result<()>
 {
@@ -135,20 +191,75 @@ impl<T> NonNullAligned<T> {
     }
 }
 
+/// Wraps a `NonNullAligned::from_arg` failure with a frame naming the argument, so a single top-level error still
+/// carries the root cause (null vs. misaligned) alongside which argument it was.
+fn context_arg<T>(arg: &'static str, result: Result<T>) -> Result<T> {
+    result.map_err(|e| {
+        let code = e.code();
+        e.context(code, format!("invalid argument `{arg}`"))
+    })
+}
+
 macro_rules! non_null_arg {
     ($arg:ident) => {
-        let $arg = NonNullAligned::from_arg(stringify!($arg), $arg)?;
+        let $arg = context_arg(stringify!($arg), NonNullAligned::from_arg(stringify!($arg), $arg))?;
     };
     (mut $arg:ident) => {
-        let mut $arg = NonNullAligned::from_arg(stringify!($arg), $arg)?;
+        let mut $arg = context_arg(stringify!($arg), NonNullAligned::from_arg(stringify!($arg), $arg))?;
     };
     ($arg:ident: [$typ:ty; $arg_len:ident]) => {
-        let $arg = NonNullAligned::from_arg(stringify!($arg), $arg as *mut $typ)?;
+        let $arg = context_arg(stringify!($arg), NonNullAligned::from_arg(stringify!($arg), $arg as *mut $typ))?;
         let $arg = unsafe { std::slice::from_raw_parts($arg.as_ptr(), $arg_len) };
     };
 }
 
-trait NativeArc: Sized {
+/// Number of frames in the thread's current error chain, from outermost to root cause. `0` if there is none.
+#[no_mangle]
+pub extern "C" fn cachers_current_error_depth() -> usize {
+    with_current_error(Error::depth).unwrap_or(0)
+}
+
+/// The `ErrorCode` and message of frame `index` of the thread's current error chain (`0` is outermost). Both the
+/// frame's `ErrorCode` and the chain itself are left untouched by this call, so it may be repeated for each index.
+///
+/// This deliberately does not go through [`wrap_err_call`]: its `Ok(())` path clears the thread-local chain, which
+/// would both invalidate the `out_msg` pointer we just handed back (it points into that chain's `String`) and wipe
+/// the chain out from under a caller still walking it. Instead this mirrors `cachers_current_errstr` and leaves the
+/// `Error` parked in the thread-local on success, only disturbing it to report a genuine argument error.
+#[no_mangle]
+pub extern "C" fn cachers_current_error_at(
+    index: usize,
+    out_code: *mut ErrorCode,
+    out_msg: *mut *const ffi::c_char,
+) -> ErrorCode {
+    let result = (|| -> Result<()> {
+        non_null_arg!(mut out_code);
+        non_null_arg!(mut out_msg);
+
+        let frame = with_current_error(|e| {
+            e.at(index).map(|frame| (frame.code, frame.message.as_ptr() as *const ffi::c_char))
+        }).flatten();
+
+        let Some((code, msg)) = frame else {
+            return Err(Error::new(ErrorCode::InvalidArgument, format!("no current error frame at index {index}")));
+        };
+
+        *out_code.as_mut() = code;
+        *out_msg.as_mut() = msg;
+        Ok(())
+    })();
+
+    match result {
+        Ok(()) => ErrorCode::Ok,
+        Err(e) => {
+            let code = e.code();
+            e.save_to_thread_local();
+            code
+        }
+    }
+}
+
+pub(crate) trait NativeArc: Sized {
     fn from_native_take(src: NonNullAligned<Self>) -> Arc<Self> {
         unsafe { Arc::from_raw(src.as_ref()) }
     }
@@ -171,11 +282,17 @@ trait NativeArc: Sized {
 /// The database.
 pub struct Database {
     _requests: Mutex<Option<i32>>,
+    workers: WorkerPool,
+    backend: Arc<dyn RequestBackend>,
 }
 
 impl Database {
     pub fn new() -> Result<Arc<Self>> {
-        Ok(Arc::new(Database { _requests: Default::default() }))
+        Ok(Arc::new(Database {
+            _requests: Default::default(),
+            workers: WorkerPool::new(DEFAULT_WORKER_COUNT),
+            backend: Arc::new(EchoBackend),
+        }))
     }
 
     pub(crate) fn from_native_take(src: NonNullAligned<Self>) -> Arc<Self> {
@@ -193,20 +310,74 @@ impl Database {
     }
 
     pub(crate) fn get(self: &Arc<Self>, key: &[u8]) -> Arc<ResponseInner> {
-        let response = ResponseInner {
+        let response = Arc::new(ResponseInner {
             header: Bytes::copy_from_slice(key),
-            data: Mutex::new(ResponseInnerData::Some(Bytes::copy_from_slice(key))),
-        };
-        Arc::new(response)
+            data: Mutex::new(ResponseInnerData::None),
+        });
+
+        let backend = self.backend.clone();
+        let key = Bytes::copy_from_slice(key);
+        let fetching = response.clone();
+        self.workers.spawn(move || {
+            let result = backend.fetch(&key).map_err(|e| {
+                let code = e.code();
+                e.context(code, "background fetch failed")
+            });
+            ResponseInner::complete(fetching, result);
+        });
+
+        response
     }
 }
 
+/// Negotiate ABI/feature compatibility without opening a database. [`cachers_open`] performs the same negotiation
+/// internally, but a caller that wants to check compatibility up front (e.g. before allocating anything) can call
+/// this directly.
 #[no_mangle]
-pub extern "C" fn cachers_open(out: *mut *mut Database) -> ErrorCode {
+pub extern "C" fn cachers_negotiate(
+    requested: *const CachersVersion,
+    out_agreed: *mut CachersVersion,
+) -> ErrorCode {
     wrap_err_call(|| {
+        let requested = context_arg(
+            "requested",
+            NonNullAligned::from_arg("requested", requested as *mut CachersVersion),
+        )?;
+        non_null_arg!(mut out_agreed);
+
+        let agreed = version::negotiate(requested.as_ref()).map_err(|e| {
+            let code = e.code();
+            e.context(code, "cachers_negotiate")
+        })?;
+        *out_agreed.as_mut() = agreed;
+        Ok(())
+    })
+}
+
+/// Open a database, gated on a successful ABI/feature negotiation: a stale header paired with a newer/older `.so`
+/// fails loudly here rather than corrupting `ResponseInfo` reads down the line. `out_agreed_version` is filled in with the
+/// negotiated version on success.
+#[no_mangle]
+pub extern "C" fn cachers_open(
+    requested_version: *const CachersVersion,
+    out_agreed_version: *mut CachersVersion,
+    out: *mut *mut Database,
+) -> ErrorCode {
+    wrap_err_call(|| {
+        let requested_version = context_arg(
+            "requested_version",
+            NonNullAligned::from_arg("requested_version", requested_version as *mut CachersVersion),
+        )?;
+        non_null_arg!(mut out_agreed_version);
         non_null_arg!(mut out);
 
+        let agreed = version::negotiate(requested_version.as_ref()).map_err(|e| {
+            let code = e.code();
+            e.context(code, "cachers_open")
+        })?;
+
         let db = Database::new()?;
+        *out_agreed_version.as_mut() = agreed;
         *out.as_mut() = db.into_native();
         Ok(())
     })
@@ -217,13 +388,8 @@ pub extern "C" fn cachers_release(db: *mut Database) -> ErrorCode {
     wrap_err_call(|| {
         non_null_arg!(db);
         let db = Database::from_native_take(db);
-        let db_weak = Arc::downgrade(&db);
         drop(db);
 
-        if let Some(_) = db_weak.upgrade() {
-            println!("Database released while still in use!");
-        }
-
         Ok(())
     })
 }
@@ -234,16 +400,94 @@ pub struct ResponseInner {
 }
 
 enum ResponseInnerData {
+    /// The fetch is still running and nobody is waiting on it yet.
     None,
     Some(Bytes),
+    /// The raw bytes have been validated as an archived record by `cachers_response_data_validate`; `archive`'s
+    /// field ranges are guaranteed to lie inside `bytes`.
+    Archived {
+        bytes: Bytes,
+        archive: Archive,
+    },
+    /// The fetch failed; the error is surfaced through [`ResponseInfo::error_code`] rather than panicking.
+    Error(Error),
     Callback {
-        func: unsafe extern "C" fn(response: *const ResponseInfo, cxt: *mut ffi::c_void),
+        func: CallbackFn,
         context: *mut ffi::c_void,
-    }
+    },
+    /// Bound to a switchless completion queue rather than a callback; `fallback` is invoked if the queue is (or
+    /// stays) full past `SWITCHLESS_PUSH_SPIN_ATTEMPTS` retries.
+    Queue {
+        queue: Arc<CompletionQueue>,
+        fallback: Option<(CallbackFn, *mut ffi::c_void)>,
+    },
 }
 
+// safety: the only raw pointers here are opaque C callback contexts handed to us by the caller across the FFI
+// boundary; moving them to a worker thread to complete a response is the entire point of the async pipeline, and
+// access is always serialized through `ResponseInner::data`'s mutex
+unsafe impl Send for ResponseInnerData {}
+
 impl NativeArc for ResponseInner {}
 
+impl ResponseInner {
+    /// Lock `self.data`, attaching `context` (typically the FFI entry point) to a poisoned-lock error rather than
+    /// panicking across the C boundary.
+    fn lock_data(&self, context: &str) -> Result<MutexGuard<'_, ResponseInnerData>> {
+        self.data.lock().map_err(|_| {
+            Error::new(ErrorCode::InvalidArgument, "response data mutex was poisoned")
+                .context(ErrorCode::InvalidArgument, context)
+        })
+    }
+
+    /// Called from a worker thread once a fetch finishes. Swaps the result into `response.data` and, if a caller
+    /// was already waiting on a callback, invokes it exactly once -- transferring a token ref to the C++ side.
+    ///
+    /// If `response.data` is poisoned there's no FFI caller on the stack to report the error to, so this just gives
+    /// up on completing the response rather than panicking a worker thread.
+    fn complete(response: Arc<Self>, result: Result<Bytes>) {
+        let Ok(mut datalock) = response.lock_data("ResponseInner::complete") else { return };
+        let previous = mem::replace(
+            datalock.deref_mut(),
+            match result {
+                Ok(bytes) => ResponseInnerData::Some(bytes),
+                Err(err) => ResponseInnerData::Error(err),
+            },
+        );
+        drop(datalock);
+
+        match previous {
+            ResponseInnerData::Callback { func, context } => {
+                let Ok(info) = ResponseInfo::from_response(response, "ResponseInner::complete") else { return };
+                unsafe { func(&info, context) };
+            }
+            ResponseInnerData::Queue { queue, fallback } => {
+                let token = ResponseInner::into_native(response.clone());
+                let mut pushed = queue.push(token);
+                let mut attempts = 0;
+                while !pushed && attempts < SWITCHLESS_PUSH_SPIN_ATTEMPTS {
+                    thread::yield_now();
+                    pushed = queue.push(token);
+                    attempts += 1;
+                }
+
+                if !pushed {
+                    // safety: `token` was produced via `into_native` just above and hasn't been handed to C++ yet
+                    drop(unsafe { Arc::from_raw(token as *const ResponseInner) });
+                    if let Some((func, context)) = fallback {
+                        let Ok(info) = ResponseInfo::from_response(response, "ResponseInner::complete") else {
+                            return;
+                        };
+                        unsafe { func(&info, context) };
+                    }
+                }
+            }
+            ResponseInnerData::None | ResponseInnerData::Some(_)
+            | ResponseInnerData::Archived { .. } | ResponseInnerData::Error(_) => {}
+        }
+    }
+}
+
 #[repr(C)]
 pub struct ResponseInfo {
     token: *mut ResponseInner,
@@ -257,17 +501,20 @@ pub struct ResponseInfo {
 
 impl ResponseInfo {
     fn from_locked(value: Arc<ResponseInner>, datalock: MutexGuard<'_, ResponseInnerData>) -> Self {
-        let data = match datalock.deref() {
-            ResponseInnerData::None => None,
-            ResponseInnerData::Some(b) => Some(b),
-            _ => todo!(),
+        let (data_state, data, error_code) = match datalock.deref() {
+            ResponseInnerData::None => (DataState::InProgress, None, ErrorCode::Ok),
+            ResponseInnerData::Callback { .. } => (DataState::InProgress, None, ErrorCode::Ok),
+            ResponseInnerData::Queue { .. } => (DataState::InProgress, None, ErrorCode::Ok),
+            ResponseInnerData::Some(b) => (DataState::Complete, Some(b), ErrorCode::Ok),
+            ResponseInnerData::Archived { bytes, .. } => (DataState::Complete, Some(bytes), ErrorCode::Ok),
+            ResponseInnerData::Error(e) => (DataState::Error, None, e.code()),
         };
         let mut out = ResponseInfo {
             token: ptr::null_mut(),
-            error_code: ErrorCode::Ok,
+            error_code,
             header: value.header.as_ptr() as *const _,
             header_size: value.header.len(),
-            data_state: DataState::Complete,
+            data_state,
             data: data.map_or(ptr::null(), |x| x.as_ptr() as *const _),
             data_size: data.map_or(0, |x| x.len()),
         };
@@ -275,32 +522,14 @@ impl ResponseInfo {
         out.token = ResponseInner::into_native(value);
         out
     }
-}
 
-impl From<Arc<ResponseInner>> for ResponseInfo {
-    fn from(value: Arc<ResponseInner>) -> Self {
-        // HACK
-        Self::from_locked(value.clone(), value.data.lock().unwrap())
-        /*
-        let datalock = value.data.lock().unwrap();
-        let data = match datalock.deref() {
-            ResponseInnerData::None => None,
-            ResponseInnerData::Some(b) => Some(b),
-            _ => todo!(),
-        };
-        let mut out = ResponseInfo {
-            token: ptr::null_mut(),
-            error_code: ErrorCode::Ok,
-            header: value.header.as_ptr() as *const _,
-            header_size: value.header.len(),
-            data_state: DataState::Complete,
-            data: data.map_or(ptr::null(), |x| x.as_ptr() as *const _),
-            data_size: data.map_or(0, |x| x.len()),
-        };
-        drop(datalock);
-        out.token = ResponseInner::into_native(value);
-        out
-        */
+    /// Build a [`ResponseInfo`] straight from a [`ResponseInner`], locking it first. `context` names the caller,
+    /// for a poisoned-lock error (see [`ResponseInner::lock_data`]).
+    fn from_response(value: Arc<ResponseInner>, context: &str) -> Result<Self> {
+        // HACK: `datalock` borrows `value`, so `from_locked` (which consumes both the guard and an Arc) gets a clone
+        // of the Arc rather than `value` itself.
+        let datalock = value.lock_data(context)?;
+        Ok(Self::from_locked(value.clone(), datalock))
     }
 }
 
@@ -310,7 +539,9 @@ pub enum DataState {
     None,
     /// The data has been fetched.
     Complete,
+    /// The fetch is still running on a worker thread.
     InProgress,
+    /// The fetch failed; see `ResponseInfo::error_code`.
     Error,
 }
 
@@ -329,7 +560,7 @@ pub extern "C" fn cachers_get(
         let db = Database::from_native(db);
         let resp = db.get(key);
 
-        *out.as_mut() = ResponseInfo::from(resp);
+        *out.as_mut() = ResponseInfo::from_response(resp, "cachers_get")?;
 
         Ok(())
     })
@@ -338,7 +569,7 @@ pub extern "C" fn cachers_get(
 #[no_mangle]
 pub extern "C" fn cachers_response_get_or_bind(
     token: *mut ResponseInner,
-    callback: Option<unsafe extern "C" fn(response: *const ResponseInfo, cxt: *mut ffi::c_void)>,
+    callback: Option<CallbackFn>,
     callback_cxt: *mut ffi::c_void,
     maybe_out: *mut ResponseInfo,
 ) -> ErrorCode {
@@ -350,18 +581,198 @@ pub extern "C" fn cachers_response_get_or_bind(
         non_null_arg!(mut maybe_out);
 
         let response = ResponseInner::from_native(token);
-        let mut datalock = response.data.lock().unwrap();
+        let mut datalock = response.lock_data("cachers_response_get_or_bind")?;
         match datalock.deref() {
             ResponseInnerData::None => {
                 *datalock.deref_mut() = ResponseInnerData::Callback { func: callback, context: callback_cxt };
-                return Ok(());
+                Ok(())
+            }
+            ResponseInnerData::Callback { .. } | ResponseInnerData::Queue { .. } => {
+                Err(Error::new(ErrorCode::HasData, "this response is already bound to a callback or completion queue"))
+            }
+            ResponseInnerData::Some(_) | ResponseInnerData::Archived { .. } | ResponseInnerData::Error(_) => {
+                // HACK
+                *maybe_out.as_mut() = ResponseInfo::from_locked(response.clone(), datalock);
+                Ok(())
             }
-            ResponseInnerData::Some(_) => {
+        }
+    })
+}
+
+/// Variant of [`cachers_response_get_or_bind`] that binds the response to a switchless completion queue instead of
+/// invoking a callback. `fallback`/`fallback_cxt` are optional and, if given, are invoked the same way a plain
+/// callback would be if the queue stays full past the configured retry threshold.
+#[no_mangle]
+pub extern "C" fn cachers_response_get_or_bind_queue(
+    token: *mut ResponseInner,
+    queue: *mut CompletionQueue,
+    fallback: Option<CallbackFn>,
+    fallback_cxt: *mut ffi::c_void,
+    maybe_out: *mut ResponseInfo,
+) -> ErrorCode {
+    wrap_err_call(|| {
+        non_null_arg!(token);
+        non_null_arg!(queue);
+        non_null_arg!(mut maybe_out);
+
+        let queue = CompletionQueue::from_native(queue);
+        let fallback = fallback.map(|func| (func, fallback_cxt));
+
+        let response = ResponseInner::from_native(token);
+        let mut datalock = response.lock_data("cachers_response_get_or_bind_queue")?;
+        match datalock.deref() {
+            ResponseInnerData::None => {
+                *datalock.deref_mut() = ResponseInnerData::Queue { queue, fallback };
+                Ok(())
+            }
+            ResponseInnerData::Callback { .. } | ResponseInnerData::Queue { .. } => {
+                Err(Error::new(ErrorCode::HasData, "this response is already bound to a callback or completion queue"))
+            }
+            ResponseInnerData::Some(_) | ResponseInnerData::Archived { .. } | ResponseInnerData::Error(_) => {
                 // HACK
                 *maybe_out.as_mut() = ResponseInfo::from_locked(response.clone(), datalock);
-                return Ok(())
+                Ok(())
+            }
+        }
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn cachers_completion_queue_create(
+    capacity: usize,
+    out: *mut *mut CompletionQueue,
+) -> ErrorCode {
+    wrap_err_call(|| {
+        non_null_arg!(mut out);
+
+        let queue = Arc::new(CompletionQueue::new(capacity)?);
+        *out.as_mut() = queue.into_native();
+        Ok(())
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn cachers_completion_queue_release(queue: *mut CompletionQueue) -> ErrorCode {
+    wrap_err_call(|| {
+        non_null_arg!(queue);
+        drop(CompletionQueue::from_native_take(queue));
+        Ok(())
+    })
+}
+
+/// Drain up to `max` completed tokens from `queue` into `out_tokens`, without crossing into a Rust-managed
+/// callback. Each returned token is owned by the caller, same as one returned via a bound callback -- release it
+/// with [`cachers_response_token_release`].
+#[no_mangle]
+pub extern "C" fn cachers_completion_poll(
+    queue: *mut CompletionQueue,
+    out_tokens: *mut *mut ResponseInner,
+    max: usize,
+    out_count: *mut usize,
+) -> ErrorCode {
+    wrap_err_call(|| {
+        non_null_arg!(queue);
+        non_null_arg!(mut out_count);
+
+        let queue = CompletionQueue::from_native(queue);
+        let mut count = 0;
+        if max > 0 {
+            non_null_arg!(out_tokens);
+            let out_tokens = unsafe { std::slice::from_raw_parts_mut(out_tokens.as_ptr(), max) };
+            while count < max {
+                match queue.pop() {
+                    Some(token) => {
+                        out_tokens[count] = token;
+                        count += 1;
+                    }
+                    None => break,
+                }
+            }
+        }
+        *out_count.as_mut() = count;
+
+        Ok(())
+    })
+}
+
+/// Validate a completed response's bytes as an archived record, turning `ResponseInnerData::Some(Bytes)` into a
+/// safe zero-copy structured channel. On success, subsequent calls to `cachers_response_archive_field` read
+/// straight out of the validated buffer with no further bounds checking or copying. On failure, the response
+/// transitions to `DataState::Error` the same way a failed fetch does.
+#[no_mangle]
+pub extern "C" fn cachers_response_data_validate(token: *mut ResponseInner) -> ErrorCode {
+    wrap_err_call(|| {
+        non_null_arg!(token);
+
+        let response = ResponseInner::from_native(token);
+        let mut datalock = response.lock_data("cachers_response_data_validate")?;
+        match datalock.deref() {
+            ResponseInnerData::Some(bytes) => {
+                let bytes = bytes.clone();
+                match Archive::validate(&bytes) {
+                    Ok(archive) => *datalock.deref_mut() = ResponseInnerData::Archived { bytes, archive },
+                    Err(e) => *datalock.deref_mut() = ResponseInnerData::Error(e),
+                }
+                Ok(())
+            }
+            // already validated, or already failed (fetch or a previous validation) -- nothing more to do
+            ResponseInnerData::Archived { .. } | ResponseInnerData::Error(_) => Ok(()),
+            ResponseInnerData::None | ResponseInnerData::Callback { .. } | ResponseInnerData::Queue { .. } => {
+                Err(Error::new(ErrorCode::Empty, "response has no data yet to validate"))
+            }
+        }
+    })
+}
+
+/// Number of fields in a validated archive. Fails with `ErrorCode::InvalidArgument` if the response hasn't been
+/// validated yet -- call `cachers_response_data_validate` first.
+#[no_mangle]
+pub extern "C" fn cachers_response_archive_field_count(
+    token: *mut ResponseInner,
+    out_count: *mut usize,
+) -> ErrorCode {
+    wrap_err_call(|| {
+        non_null_arg!(token);
+        non_null_arg!(mut out_count);
+
+        let response = ResponseInner::from_native(token);
+        let datalock = response.lock_data("cachers_response_archive_field_count")?;
+        match datalock.deref() {
+            ResponseInnerData::Archived { archive, .. } => {
+                *out_count.as_mut() = archive.field_count();
+                Ok(())
+            }
+            _ => Err(Error::new(ErrorCode::InvalidArgument, "response has not been validated as an archive")),
+        }
+    })
+}
+
+/// Read field `index` of a validated archive directly out of the underlying buffer -- no copy, no further
+/// validation. The pointer is valid for as long as `token` is (i.e. until `cachers_response_token_release`).
+#[no_mangle]
+pub extern "C" fn cachers_response_archive_field(
+    token: *mut ResponseInner,
+    index: usize,
+    out_ptr: *mut *const ffi::c_void,
+    out_len: *mut usize,
+) -> ErrorCode {
+    wrap_err_call(|| {
+        non_null_arg!(token);
+        non_null_arg!(mut out_ptr);
+        non_null_arg!(mut out_len);
+
+        let response = ResponseInner::from_native(token);
+        let datalock = response.lock_data("cachers_response_archive_field")?;
+        match datalock.deref() {
+            ResponseInnerData::Archived { bytes, archive } => {
+                let Some(field) = archive.field(bytes, index) else {
+                    return Err(Error::new(ErrorCode::InvalidArgument, format!("no archive field at index {index}")));
+                };
+                *out_ptr.as_mut() = field.as_ptr() as *const _;
+                *out_len.as_mut() = field.len();
+                Ok(())
             }
-            _ => todo!(),
+            _ => Err(Error::new(ErrorCode::InvalidArgument, "response has not been validated as an archive")),
         }
     })
 }
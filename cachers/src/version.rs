@@ -0,0 +1,66 @@
+//! ABI/feature version negotiation between the Rust library and whatever C++ binary links against it.
+//!
+//! `cachers.h` is generated by cbindgen from whatever `ResponseInfo`/`DataState`/`ErrorCode` look like *right now*,
+//! so a C++ project built against an older header and linked against a newer `.so` (or vice versa) has no way to
+//! know its struct layouts or assumptions about optional subsystems still hold. [`cachers_negotiate`] (and
+//! [`cachers_open`](crate::cachers_open), which calls the same logic) give the caller a chance to find out before
+//! any `ResponseInfo` gets read.
+
+use crate::{Error, ErrorCode, Result};
+
+/// Highest ABI major version this build of the library understands. A mismatch here means struct layouts may
+/// differ and is always fatal.
+pub(crate) const ABI_MAJOR: u32 = 1;
+/// Highest ABI minor version understood; minor versions are additive (new, optional fields) so older minors remain
+/// compatible.
+pub(crate) const ABI_MINOR: u32 = 0;
+
+/// Switchless completion queue (`cachers_completion_queue_create` & friends).
+pub const CACHERS_FEATURE_SWITCHLESS_QUEUE: u32 = 1 << 0;
+/// Validated archived-value records (`cachers_response_data_validate` & friends).
+pub const CACHERS_FEATURE_ARCHIVED_VALUES: u32 = 1 << 1;
+
+/// Feature bits this build actually implements; intersected with whatever the caller requests.
+pub(crate) const SUPPORTED_FEATURES: u32 = CACHERS_FEATURE_SWITCHLESS_QUEUE | CACHERS_FEATURE_ARCHIVED_VALUES;
+
+const LIB_MAJOR: u16 = 0;
+const LIB_MINOR: u16 = 1;
+const LIB_PATCH: u16 = 0;
+
+/// A version/feature vector exchanged during negotiation. `features` is a bitset of the `CACHERS_FEATURE_*`
+/// constants; unrecognized bits are simply dropped by whichever side doesn't understand them.
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct CachersVersion {
+    pub abi_major: u32,
+    pub abi_minor: u32,
+    pub features: u32,
+    pub lib_major: u16,
+    pub lib_minor: u16,
+    pub lib_patch: u16,
+}
+
+/// Intersect `requested` against what this build supports, returning the agreed-upon version or an
+/// [`ErrorCode::InvalidArgument`] if the ABI major versions can't talk to each other at all. Reached from both
+/// [`cachers_negotiate`](crate::cachers_negotiate) and [`cachers_open`](crate::cachers_open), so this attaches no
+/// entry-point context itself -- the caller does. (`Archive::validate`, by contrast, names
+/// `cachers_response_data_validate` itself, its only caller.)
+pub(crate) fn negotiate(requested: &CachersVersion) -> Result<CachersVersion> {
+    if requested.abi_major != ABI_MAJOR {
+        return Err(Error::new(ErrorCode::InvalidArgument, format!(
+            "ABI mismatch: library supports major version {ABI_MAJOR}, caller requested {}",
+            requested.abi_major
+        )));
+    }
+
+    Ok(CachersVersion {
+        abi_major: ABI_MAJOR,
+        // trivially `ABI_MINOR` while it's `0`, but keeps behaving correctly once this build's minor version grows
+        #[allow(clippy::unnecessary_min_or_max)]
+        abi_minor: ABI_MINOR.min(requested.abi_minor),
+        features: SUPPORTED_FEATURES & requested.features,
+        lib_major: LIB_MAJOR,
+        lib_minor: LIB_MINOR,
+        lib_patch: LIB_PATCH,
+    })
+}
@@ -0,0 +1,44 @@
+//! A small, fixed-size worker pool used to run fetches off the thread that called into the library.
+
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread::{self, JoinHandle};
+
+type Job = Box<dyn FnOnce() + Send + 'static>;
+
+/// The number of worker threads spun up by a freshly-opened [`Database`](crate::Database).
+pub(crate) const DEFAULT_WORKER_COUNT: usize = 4;
+
+pub(crate) struct WorkerPool {
+    sender: mpsc::Sender<Job>,
+    // kept alive for the lifetime of the pool; never read, but dropping it joins nothing on its own
+    _workers: Vec<JoinHandle<()>>,
+}
+
+impl WorkerPool {
+    pub(crate) fn new(size: usize) -> Self {
+        let (sender, receiver) = mpsc::channel::<Job>();
+        let receiver = Arc::new(Mutex::new(receiver));
+
+        let workers = (0..size.max(1))
+            .map(|_| {
+                let receiver = Arc::clone(&receiver);
+                thread::spawn(move || loop {
+                    let job = receiver.lock().unwrap().recv();
+                    match job {
+                        Ok(job) => job(),
+                        // sender side dropped (pool torn down): nothing left to do
+                        Err(_) => break,
+                    }
+                })
+            })
+            .collect();
+
+        Self { sender, _workers: workers }
+    }
+
+    /// Schedule `job` to run on the next free worker thread.
+    pub(crate) fn spawn(&self, job: impl FnOnce() + Send + 'static) {
+        // if every worker has already shut down there is no one left to report the error to; drop the job
+        let _ = self.sender.send(Box::new(job));
+    }
+}